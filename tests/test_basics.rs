@@ -18,7 +18,7 @@ fn setup_contract() -> (SHLDContract, AccountId) {
     let context = get_context(account_id.clone());
     testing_env!(context);
     
-    let contract = SHLDContract::new();
+    let contract = SHLDContract::new(account_id.clone(), None, None, None);
     (contract, account_id)
 }
 
@@ -72,6 +72,7 @@ fn test_create_proposal() {
     let proposal_id = contract.create_proposal(
         "Test Proposal".to_string(),
         "Test Description".to_string(),
+        None,
     );
 
     let proposal = contract.get_proposal(proposal_id).unwrap();
@@ -91,6 +92,7 @@ fn test_create_proposal_non_token_holder() {
     contract.create_proposal(
         "Test Proposal".to_string(),
         "Test Description".to_string(),
+        None,
     );
 }
 
@@ -107,9 +109,10 @@ fn test_vote_on_proposal() {
     let proposal_id = contract.create_proposal(
         "Test Proposal".to_string(),
         "Test Description".to_string(),
+        None,
     );
 
-    contract.vote(proposal_id, true);
+    contract.vote(proposal_id, true, None);
 
     let proposal = contract.get_proposal(proposal_id).unwrap();
     assert_eq!(proposal.votes_for, NearToken::from_near(1));
@@ -131,10 +134,11 @@ fn test_vote_twice() {
     let proposal_id = contract.create_proposal(
         "Test Proposal".to_string(),
         "Test Description".to_string(),
+        None,
     );
 
-    contract.vote(proposal_id, true);
-    contract.vote(proposal_id, true); // Should panic
+    contract.vote(proposal_id, true, None);
+    contract.vote(proposal_id, true, None); // Should panic
 }*/
 
 #[test]
@@ -154,14 +158,15 @@ fn test_proposal_passed() {
     let proposal_id = contract.create_proposal(
         "Test Proposal".to_string(),
         "Test Description".to_string(),
+        None,
     );
 
     // Vote with all three accounts
-    contract.vote(proposal_id, true);
+    contract.vote(proposal_id, true, None);
     testing_env!(get_context(accounts(1)));
-    contract.vote(proposal_id, true);
+    contract.vote(proposal_id, true, None);
     testing_env!(get_context(accounts(2)));
-    contract.vote(proposal_id, false);
+    contract.vote(proposal_id, false, None);
 
     let proposal = contract.get_proposal(proposal_id).unwrap();
     assert_eq!(proposal.status, ProposalStatus::Passed);
@@ -182,10 +187,12 @@ fn test_get_all_proposals() {
     let proposal_id1 = contract.create_proposal(
         "Proposal 1".to_string(),
         "Description 1".to_string(),
+        None,
     );
     let proposal_id2 = contract.create_proposal(
         "Proposal 2".to_string(),
         "Description 2".to_string(),
+        None,
     );
 
     let proposals = contract.get_all_proposals();